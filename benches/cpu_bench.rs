@@ -0,0 +1,34 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use nes_rs::asm::Asm;
+use nes_rs::cpu::CPU;
+use std::hint::black_box;
+
+fn straight_line_program() -> Vec<u8> {
+    let mut asm = Asm::new();
+    for _ in 0..0x800 {
+        asm = asm
+            .lda_imm(0x01)
+            .tax()
+            .inx()
+            .adc_imm(0x02)
+            .sta_zp(0x10)
+            .eor_zp(0x10)
+            .iny()
+            .asl_acc();
+    }
+    asm.brk().assemble()
+}
+
+fn bench_dispatch(c: &mut Criterion) {
+    let program = straight_line_program();
+    let mut cpu = CPU::new();
+    c.bench_function("dispatch_straight_line", |b| {
+        b.iter(|| {
+            cpu.load_and_run(black_box(program.clone()));
+            black_box(cpu.reg_a)
+        })
+    });
+}
+
+criterion_group!(benches, bench_dispatch);
+criterion_main!(benches);
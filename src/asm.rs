@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+/* Builder for 6502 test programs, e.g.
+ * Asm::new().lda_imm(0xc0).tax().inx().brk().assemble()
+ * Branches take a label which is resolved in assemble(). */
+#[derive(Default)]
+pub struct Asm {
+    code: Vec<u8>,
+    labels: HashMap<String, usize>,
+    fixups: Vec<(usize, String)>,
+}
+
+macro_rules! implied {
+    ($($name:ident => $code:expr),* $(,)?) => {
+        $(
+            pub fn $name(self) -> Self {
+                self.emit(&[$code])
+            }
+        )*
+    };
+}
+
+macro_rules! operand_u8 {
+    ($($name:ident => $code:expr),* $(,)?) => {
+        $(
+            pub fn $name(self, operand: u8) -> Self {
+                self.emit(&[$code, operand])
+            }
+        )*
+    };
+}
+
+macro_rules! operand_u16 {
+    ($($name:ident => $code:expr),* $(,)?) => {
+        $(
+            pub fn $name(self, addr: u16) -> Self {
+                let [lo, hi] = addr.to_le_bytes();
+                self.emit(&[$code, lo, hi])
+            }
+        )*
+    };
+}
+
+macro_rules! branch {
+    ($($name:ident => $code:expr),* $(,)?) => {
+        $(
+            pub fn $name(self, label: &str) -> Self {
+                self.branch($code, label)
+            }
+        )*
+    };
+}
+
+impl Asm {
+    pub fn new() -> Self {
+        Asm::default()
+    }
+
+    pub fn label(mut self, name: &str) -> Self {
+        if self
+            .labels
+            .insert(name.to_string(), self.code.len())
+            .is_some()
+        {
+            panic!("Label {} is defined twice", name);
+        }
+        self
+    }
+
+    pub fn bytes(self, data: &[u8]) -> Self {
+        self.emit(data)
+    }
+
+    pub fn assemble(mut self) -> Vec<u8> {
+        for (pos, label) in &self.fixups {
+            let target = *self
+                .labels
+                .get(label)
+                .unwrap_or_else(|| panic!("Label {} is not defined", label));
+            /* Offset is relative to the instruction following the branch */
+            let offset = target as isize - (*pos as isize + 1);
+            let offset = i8::try_from(offset)
+                .unwrap_or_else(|_| panic!("Branch to {} is out of range", label));
+            self.code[*pos] = offset as u8;
+        }
+        self.code
+    }
+
+    fn emit(mut self, data: &[u8]) -> Self {
+        self.code.extend_from_slice(data);
+        self
+    }
+
+    fn branch(mut self, code: u8, label: &str) -> Self {
+        self.code.push(code);
+        self.fixups.push((self.code.len(), label.to_string()));
+        self.code.push(0);
+        self
+    }
+
+    implied! {
+        brk => 0x00,
+        nop => 0xea,
+        /* Accumulator */
+        asl_acc => 0x0a,
+        lsr_acc => 0x4a,
+        rol_acc => 0x2a,
+        ror_acc => 0x6a,
+        /* Flags */
+        clc => 0x18,
+        cld => 0xd8,
+        cli => 0x58,
+        clv => 0xb8,
+        sec => 0x38,
+        sed => 0xf8,
+        sei => 0x78,
+        /* Registers */
+        tax => 0xaa,
+        tay => 0xa8,
+        tsx => 0xba,
+        txa => 0x8a,
+        txs => 0x9a,
+        tya => 0x98,
+        inx => 0xe8,
+        iny => 0xc8,
+        dex => 0xca,
+        dey => 0x88,
+        /* Stack */
+        pha => 0x48,
+        php => 0x08,
+        pla => 0x68,
+        plp => 0x28,
+        rti => 0x40,
+        rts => 0x60,
+    }
+
+    operand_u8! {
+        adc_imm => 0x69, adc_zp => 0x65, adc_zpx => 0x75, adc_indx => 0x61, adc_indy => 0x71,
+        and_imm => 0x29, and_zp => 0x25, and_zpx => 0x35, and_indx => 0x21, and_indy => 0x31,
+        asl_zp => 0x06, asl_zpx => 0x16,
+        bit_zp => 0x24,
+        cmp_imm => 0xc9, cmp_zp => 0xc5, cmp_zpx => 0xd5, cmp_indx => 0xc1, cmp_indy => 0xd1,
+        cpx_imm => 0xe0, cpx_zp => 0xe4,
+        cpy_imm => 0xc0, cpy_zp => 0xc4,
+        dec_zp => 0xc6, dec_zpx => 0xd6,
+        eor_imm => 0x49, eor_zp => 0x45, eor_zpx => 0x55, eor_indx => 0x41, eor_indy => 0x51,
+        inc_zp => 0xe6, inc_zpx => 0xf6,
+        lda_imm => 0xa9, lda_zp => 0xa5, lda_zpx => 0xb5, lda_indx => 0xa1, lda_indy => 0xb1,
+        ldx_imm => 0xa2, ldx_zp => 0xa6, ldx_zpy => 0xb6,
+        ldy_imm => 0xa0, ldy_zp => 0xa4, ldy_zpx => 0xb4,
+        lsr_zp => 0x46, lsr_zpx => 0x56,
+        ora_imm => 0x09, ora_zp => 0x05, ora_zpx => 0x15, ora_indx => 0x01, ora_indy => 0x11,
+        rol_zp => 0x26, rol_zpx => 0x36,
+        ror_zp => 0x66, ror_zpx => 0x76,
+        sbc_imm => 0xe9, sbc_zp => 0xe5, sbc_zpx => 0xf5, sbc_indx => 0xe1, sbc_indy => 0xf1,
+        sta_zp => 0x85, sta_zpx => 0x95, sta_indx => 0x81, sta_indy => 0x91,
+        stx_zp => 0x86, stx_zpy => 0x96,
+        sty_zp => 0x84, sty_zpx => 0x94,
+    }
+
+    operand_u16! {
+        adc_abs => 0x6d, adc_absx => 0x7d, adc_absy => 0x79,
+        and_abs => 0x2d, and_absx => 0x3d, and_absy => 0x39,
+        asl_abs => 0x0e, asl_absx => 0x1e,
+        bit_abs => 0x2c,
+        cmp_abs => 0xcd, cmp_absx => 0xdd, cmp_absy => 0xd9,
+        cpx_abs => 0xec,
+        cpy_abs => 0xcc,
+        dec_abs => 0xce, dec_absx => 0xde,
+        eor_abs => 0x4d, eor_absx => 0x5d, eor_absy => 0x59,
+        inc_abs => 0xee, inc_absx => 0xfe,
+        jmp_abs => 0x4c, jmp_ind => 0x6c,
+        jsr => 0x20,
+        lda_abs => 0xad, lda_absx => 0xbd, lda_absy => 0xb9,
+        ldx_abs => 0xae, ldx_absy => 0xbe,
+        ldy_abs => 0xac, ldy_absx => 0xbc,
+        lsr_abs => 0x4e, lsr_absx => 0x5e,
+        ora_abs => 0x0d, ora_absx => 0x1d, ora_absy => 0x19,
+        rol_abs => 0x2e, rol_absx => 0x3e,
+        ror_abs => 0x6e, ror_absx => 0x7e,
+        sbc_abs => 0xed, sbc_absx => 0xfd, sbc_absy => 0xf9,
+        sta_abs => 0x8d, sta_absx => 0x9d, sta_absy => 0x99,
+        stx_abs => 0x8e,
+        sty_abs => 0x8c,
+    }
+
+    branch! {
+        bcc => 0x90,
+        bcs => 0xb0,
+        beq => 0xf0,
+        bmi => 0x30,
+        bne => 0xd0,
+        bpl => 0x10,
+        bvc => 0x50,
+        bvs => 0x70,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_assemble_operands() {
+        let program = Asm::new()
+            .lda_imm(0xc0)
+            .sta_abs(0x1234)
+            .tax()
+            .brk()
+            .assemble();
+        assert_eq!(program, vec![0xa9, 0xc0, 0x8d, 0x34, 0x12, 0xaa, 0x00]);
+    }
+
+    #[test]
+    fn test_branch_forward() {
+        let program = Asm::new()
+            .beq("skip")
+            .brk()
+            .brk()
+            .label("skip")
+            .inx()
+            .assemble();
+        assert_eq!(program, vec![0xf0, 0x02, 0x00, 0x00, 0xe8]);
+    }
+
+    #[test]
+    fn test_branch_backward() {
+        let program = Asm::new().label("loop").inx().bne("loop").assemble();
+        assert_eq!(program, vec![0xe8, 0xd0, 0xfd]);
+    }
+
+    #[test]
+    fn test_branch_range_limits() {
+        let program = Asm::new()
+            .beq("far")
+            .bytes(&[0xea; 127])
+            .label("far")
+            .assemble();
+        assert_eq!(&program[..2], &[0xf0, 0x7f]);
+
+        let program = Asm::new()
+            .label("back")
+            .bytes(&[0xea; 126])
+            .bne("back")
+            .assemble();
+        assert_eq!(&program[126..], &[0xd0, 0x80]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Branch to far is out of range")]
+    fn test_branch_forward_out_of_range() {
+        Asm::new()
+            .beq("far")
+            .bytes(&[0xea; 128])
+            .label("far")
+            .assemble();
+    }
+
+    #[test]
+    #[should_panic(expected = "Branch to back is out of range")]
+    fn test_branch_backward_out_of_range() {
+        Asm::new()
+            .label("back")
+            .bytes(&[0xea; 127])
+            .bne("back")
+            .assemble();
+    }
+
+    #[test]
+    #[should_panic(expected = "Label missing is not defined")]
+    fn test_undefined_label() {
+        Asm::new().bne("missing").assemble();
+    }
+
+    #[test]
+    #[should_panic(expected = "Label loop is defined twice")]
+    fn test_duplicate_label() {
+        Asm::new().label("loop").inx().label("loop");
+    }
+}
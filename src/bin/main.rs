@@ -1,3 +1,71 @@
+use nes_rs::trace;
+use std::{env, fs, process};
+
+const USAGE: &str = "usage: nes-rs trace-diff [--sync] <our.log> <reference.log>";
+const CONTEXT_LINES: usize = 5;
+
+fn read_log(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("failed to read {}: {}", path, e);
+        process::exit(2);
+    })
+}
+
+fn trace_diff(args: &[String]) {
+    let align = args.iter().any(|arg| arg == "--sync");
+    let paths: Vec<&String> = args.iter().filter(|arg| *arg != "--sync").collect();
+    let [ours, reference] = paths[..] else {
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    };
+
+    let result = trace::diff(&read_log(ours), &read_log(reference), CONTEXT_LINES, align);
+    if result.ours_skipped > 0 {
+        eprintln!(
+            "warning: skipped {} lines of our log to sync",
+            result.ours_skipped
+        );
+    }
+    if result.reference_skipped > 0 {
+        eprintln!(
+            "warning: skipped {} lines of reference log to sync",
+            result.reference_skipped
+        );
+    }
+
+    let Some(divergence) = result.divergence else {
+        println!("traces match");
+        return;
+    };
+
+    println!(
+        "first divergence at line {} (reference line {})",
+        divergence.ours_line, divergence.reference_line
+    );
+    for line in &divergence.context {
+        println!("   {}", line);
+    }
+    println!(
+        "-  {}",
+        divergence.ours.as_deref().unwrap_or("<end of our log>")
+    );
+    println!(
+        "+  {}",
+        divergence
+            .reference
+            .as_deref()
+            .unwrap_or("<end of reference log>")
+    );
+    process::exit(1);
+}
+
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("trace-diff") => trace_diff(&args[1..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
 }
@@ -1,2 +1,5 @@
+pub mod asm;
 pub mod cpu;
 pub mod opcodes;
+pub mod rom;
+pub mod trace;
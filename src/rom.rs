@@ -0,0 +1,131 @@
+const NES_TAG: [u8; 4] = [0x4E, 0x45, 0x53, 0x1A];
+const HEADER_SIZE: usize = 16;
+pub const PRG_ROM_BANK_SIZE: usize = 0x4000;
+pub const CHR_ROM_BANK_SIZE: usize = 0x2000;
+
+/* Offset of the reset vector within the last 16 KiB PRG bank. This is
+ * $FFFC for NROM/UxROM/MMC1-style boards with a fixed last bank; 32 KiB
+ * switching boards such as AxROM may map a different bank there. */
+const RESET_VECTOR_OFFSET: usize = PRG_ROM_BANK_SIZE - 4;
+
+/* Builds iNES images in memory, e.g.
+ * RomBuilder::new().mapper(0).prg(&bank).chr(&bank).reset_vector(0x8000).build()
+ * Banks shorter than the bank size are zero padded. */
+#[derive(Default)]
+pub struct RomBuilder {
+    mapper: u8,
+    vertical_mirroring: bool,
+    prg: Vec<Vec<u8>>,
+    chr: Vec<Vec<u8>>,
+    reset_vector: Option<u16>,
+}
+
+impl RomBuilder {
+    pub fn new() -> Self {
+        RomBuilder::default()
+    }
+
+    pub fn mapper(mut self, mapper: u8) -> Self {
+        self.mapper = mapper;
+        self
+    }
+
+    pub fn vertical_mirroring(mut self, vertical: bool) -> Self {
+        self.vertical_mirroring = vertical;
+        self
+    }
+
+    pub fn prg(mut self, bank: &[u8]) -> Self {
+        self.prg.push(Self::pad(bank, PRG_ROM_BANK_SIZE, "PRG"));
+        self
+    }
+
+    pub fn chr(mut self, bank: &[u8]) -> Self {
+        self.chr.push(Self::pad(bank, CHR_ROM_BANK_SIZE, "CHR"));
+        self
+    }
+
+    pub fn reset_vector(mut self, addr: u16) -> Self {
+        self.reset_vector = Some(addr);
+        self
+    }
+
+    pub fn build(mut self) -> Vec<u8> {
+        /* iNES requires at least one PRG bank */
+        if self.prg.is_empty() {
+            self.prg.push(vec![0; PRG_ROM_BANK_SIZE]);
+        }
+        if let Some(addr) = self.reset_vector {
+            let last = self.prg.last_mut().unwrap();
+            last[RESET_VECTOR_OFFSET..RESET_VECTOR_OFFSET + 2].copy_from_slice(&addr.to_le_bytes());
+        }
+        let prg_banks = u8::try_from(self.prg.len()).expect("Too many PRG banks");
+        let chr_banks = u8::try_from(self.chr.len()).expect("Too many CHR banks");
+
+        let mut rom = Vec::with_capacity(
+            HEADER_SIZE + self.prg.len() * PRG_ROM_BANK_SIZE + self.chr.len() * CHR_ROM_BANK_SIZE,
+        );
+        rom.extend_from_slice(&NES_TAG);
+        rom.push(prg_banks);
+        rom.push(chr_banks);
+        rom.push((self.mapper & 0x0F) << 4 | u8::from(self.vertical_mirroring));
+        rom.push(self.mapper & 0xF0);
+        rom.resize(HEADER_SIZE, 0);
+        rom.extend(self.prg.concat());
+        rom.extend(self.chr.concat());
+        rom
+    }
+
+    fn pad(bank: &[u8], size: usize, name: &str) -> Vec<u8> {
+        if bank.len() > size {
+            panic!("{} bank is larger than {:#x} bytes", name, size);
+        }
+        let mut padded = bank.to_vec();
+        padded.resize(size, 0);
+        padded
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_header() {
+        let rom = RomBuilder::new()
+            .mapper(0x42)
+            .vertical_mirroring(true)
+            .prg(&[])
+            .prg(&[])
+            .chr(&[])
+            .build();
+        assert_eq!(&rom[0..4], &NES_TAG);
+        assert_eq!(rom[4], 2);
+        assert_eq!(rom[5], 1);
+        assert_eq!(rom[6], 0x21);
+        assert_eq!(rom[7], 0x40);
+        assert_eq!(
+            rom.len(),
+            HEADER_SIZE + 2 * PRG_ROM_BANK_SIZE + CHR_ROM_BANK_SIZE
+        );
+    }
+
+    #[test]
+    fn test_prg_and_reset_vector() {
+        let rom = RomBuilder::new()
+            .prg(&[0xa9, 0x05, 0x00])
+            .reset_vector(0xc000)
+            .build();
+        assert_eq!(rom[4], 1);
+        assert_eq!(rom[5], 0);
+        assert_eq!(&rom[HEADER_SIZE..HEADER_SIZE + 3], &[0xa9, 0x05, 0x00]);
+        let vector = HEADER_SIZE + RESET_VECTOR_OFFSET;
+        assert_eq!(&rom[vector..vector + 2], &[0x00, 0xc0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "CHR bank is larger than 0x2000 bytes")]
+    fn test_oversized_bank() {
+        RomBuilder::new().chr(&[0; CHR_ROM_BANK_SIZE + 1]);
+    }
+}
@@ -0,0 +1,298 @@
+/* Timing columns differ between emulators (and between runs started
+ * at different cycles), so they are dropped before comparing. */
+
+/* Nintendulator/nestest: everything from the first of these to the end */
+const NINTENDULATOR_TIMING: [&str; 2] = ["PPU:", "CYC:"];
+/* FCEUX: leading f<frame>, c<cycles> and i<instructions> columns */
+const FCEUX_TIMING: [char; 3] = ['f', 'c', 'i'];
+/* Mesen: KEY:value fields anywhere in the line */
+const MESEN_TIMING: [&str; 5] = ["V", "H", "Fr", "Cycle", "Cyc"];
+
+#[derive(Debug, PartialEq)]
+pub enum TraceFormat {
+    Nintendulator,
+    Fceux,
+    Mesen,
+}
+
+impl TraceFormat {
+    pub fn detect(line: &str) -> Option<Self> {
+        if NINTENDULATOR_TIMING.iter().any(|col| line.contains(col)) {
+            Some(TraceFormat::Nintendulator)
+        } else if line
+            .split_whitespace()
+            .any(|token| mesen_key(token).is_some())
+        {
+            Some(TraceFormat::Mesen)
+        } else if line.split_whitespace().next().is_some_and(is_fceux_counter) {
+            Some(TraceFormat::Fceux)
+        } else {
+            None
+        }
+    }
+}
+
+fn mesen_key(token: &str) -> Option<&str> {
+    let (key, _) = token.split_once(':')?;
+    MESEN_TIMING.contains(&key).then_some(key)
+}
+
+fn is_fceux_counter(token: &str) -> bool {
+    let mut chars = token.chars();
+    chars.next().is_some_and(|c| FCEUX_TIMING.contains(&c))
+        && token.len() > 1
+        && chars.all(|c| c.is_ascii_digit())
+}
+
+pub fn normalize(line: &str) -> String {
+    let tokens: Vec<&str> = match TraceFormat::detect(line) {
+        Some(TraceFormat::Nintendulator) => {
+            let end = NINTENDULATOR_TIMING
+                .iter()
+                .filter_map(|col| line.find(col))
+                .min()
+                .unwrap_or(line.len());
+            line[..end].split_whitespace().collect()
+        }
+        Some(TraceFormat::Fceux) => line
+            .split_whitespace()
+            .skip_while(|token| is_fceux_counter(token))
+            .collect(),
+        Some(TraceFormat::Mesen) => {
+            let mut tokens = Vec::new();
+            let mut iter = line.split_whitespace();
+            while let Some(token) = iter.next() {
+                match mesen_key(token) {
+                    /* Padded fields such as "V:  0" split into two tokens */
+                    Some(key) if token.len() == key.len() + 1 => {
+                        iter.next();
+                    }
+                    Some(_) => {}
+                    None => tokens.push(token),
+                }
+            }
+            tokens
+        }
+        None => line.split_whitespace().collect(),
+    };
+    tokens.join(" ")
+}
+
+struct Line<'a> {
+    number: usize,
+    text: &'a str,
+    key: String,
+}
+
+fn lines(log: &str) -> Vec<Line<'_>> {
+    log.lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(i, text)| Line {
+            number: i + 1,
+            text,
+            key: normalize(text),
+        })
+        .collect()
+}
+
+/* Skip to the first line the logs have in common, so a reference that
+ * starts a few instructions earlier or later still lines up. Opt-in, as
+ * it can also skip past a real mismatch on the first line. */
+fn sync(ours: &[Line], reference: &[Line]) -> (usize, usize) {
+    if let Some(first) = ours.first() {
+        if let Some(j) = reference.iter().position(|l| l.key == first.key) {
+            return (0, j);
+        }
+    }
+    if let Some(first) = reference.first() {
+        if let Some(i) = ours.iter().position(|l| l.key == first.key) {
+            return (i, 0);
+        }
+    }
+    (0, 0)
+}
+
+pub struct Divergence {
+    /* 1-based line numbers in each log; one past the end if it ran out */
+    pub ours_line: usize,
+    pub reference_line: usize,
+    /* None when that log ended before the other */
+    pub ours: Option<String>,
+    pub reference: Option<String>,
+    /* Matching lines (from our log) leading up to the mismatch */
+    pub context: Vec<String>,
+}
+
+pub struct TraceDiff {
+    /* Lines skipped at the start of each log to line them up */
+    pub ours_skipped: usize,
+    pub reference_skipped: usize,
+    pub divergence: Option<Divergence>,
+}
+
+pub fn diff(ours_log: &str, reference_log: &str, context: usize, align: bool) -> TraceDiff {
+    let ours = lines(ours_log);
+    let reference = lines(reference_log);
+    let (ours_skipped, reference_skipped) = if align {
+        sync(&ours, &reference)
+    } else {
+        (0, 0)
+    };
+    let past_end = |log: &str| log.lines().count() + 1;
+
+    let mut ours_iter = ours[ours_skipped..].iter();
+    let mut reference_iter = reference[reference_skipped..].iter();
+    let mut history: Vec<String> = Vec::new();
+
+    let divergence = loop {
+        match (ours_iter.next(), reference_iter.next()) {
+            (None, None) => break None,
+            (Some(a), Some(b)) if a.key == b.key => {
+                history.push(a.text.to_string());
+                if history.len() > context {
+                    history.remove(0);
+                }
+            }
+            (a, b) => {
+                break Some(Divergence {
+                    ours_line: a.map_or_else(|| past_end(ours_log), |l| l.number),
+                    reference_line: b.map_or_else(|| past_end(reference_log), |l| l.number),
+                    ours: a.map(|l| l.text.to_string()),
+                    reference: b.map(|l| l.text.to_string()),
+                    context: history,
+                })
+            }
+        }
+    };
+
+    TraceDiff {
+        ours_skipped,
+        reference_skipped,
+        divergence,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalize_nintendulator() {
+        let a = "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7";
+        let b = "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 24 CYC:8";
+        assert_eq!(TraceFormat::detect(a), Some(TraceFormat::Nintendulator));
+        assert_eq!(
+            normalize(a),
+            "C000 4C F5 C5 JMP $C5F5 A:00 X:00 Y:00 P:24 SP:FD"
+        );
+        assert_eq!(normalize(a), normalize(b));
+    }
+
+    #[test]
+    fn test_normalize_fceux() {
+        let a = "c7      i0      A:00 X:00 Y:00 S:FD P:nvubdIzc  $C000:4C F5 C5  JMP $C5F5";
+        let b = "c8      i1      A:00 X:00 Y:00 S:FD P:nvubdIzc  $C000:4C F5 C5  JMP $C5F5";
+        assert_eq!(TraceFormat::detect(a), Some(TraceFormat::Fceux));
+        assert_eq!(
+            normalize(a),
+            "A:00 X:00 Y:00 S:FD P:nvubdIzc $C000:4C F5 C5 JMP $C5F5"
+        );
+        assert_eq!(normalize(a), normalize(b));
+
+        /* With "Log Frames count" enabled */
+        let c = "f12     c7      i0      A:00 X:00 Y:00 S:FD P:nvubdIzc  $C000:4C F5 C5  JMP $C5F5";
+        let d = "f13     c8      i1      A:00 X:00 Y:00 S:FD P:nvubdIzc  $C000:4C F5 C5  JMP $C5F5";
+        assert_eq!(TraceFormat::detect(c), Some(TraceFormat::Fceux));
+        assert_eq!(normalize(c), normalize(a));
+        assert_eq!(normalize(c), normalize(d));
+    }
+
+    #[test]
+    fn test_normalize_mesen() {
+        let a = "C000  JMP $C5F5                        A:00 X:00 Y:00 S:FD P:nvubdIzc V:0   H:21  Fr:0 Cyc:7";
+        let b = "C000  JMP $C5F5                        A:00 X:00 Y:00 S:FD P:nvubdIzc V:0   H:24  Fr:0 Cyc:8";
+        let c = "C000  JMP $C5F5                        A:00 X:00 Y:00 S:FD P:nvubdIzc V:  0 H: 27 Cycle:9";
+        assert_eq!(TraceFormat::detect(a), Some(TraceFormat::Mesen));
+        assert_eq!(
+            normalize(a),
+            "C000 JMP $C5F5 A:00 X:00 Y:00 S:FD P:nvubdIzc"
+        );
+        assert_eq!(normalize(a), normalize(b));
+        assert_eq!(normalize(a), normalize(c));
+    }
+
+    #[test]
+    fn test_normalize_keeps_registers() {
+        let a = "c7      i0      A:00 X:00 Y:00 S:FD P:nvubdIzc  $C000:4C F5 C5  JMP $C5F5";
+        let b = "c7      i0      A:01 X:00 Y:00 S:FD P:nvubdIzc  $C000:4C F5 C5  JMP $C5F5";
+        assert_ne!(normalize(a), normalize(b));
+    }
+
+    #[test]
+    fn test_diff_ignores_cycle_differences() {
+        let ours = "C000 JMP $C5F5 A:00 CYC:7\nC5F5 LDX #$00 A:00 CYC:10\n";
+        let reference = "C000 JMP $C5F5 A:00 CYC:8\nC5F5 LDX #$00 A:00 CYC:11\n";
+        assert!(diff(ours, reference, 3, false).divergence.is_none());
+    }
+
+    #[test]
+    fn test_diff_reports_first_divergence() {
+        let ours = "C000 A:00\nC002 A:01\nC004 A:02\nC006 A:03\n";
+        let reference = "C000 A:00\nC002 A:01\nC004 A:FF\nC006 A:03\n";
+        let divergence = diff(ours, reference, 1, false).divergence.unwrap();
+        assert_eq!(divergence.ours_line, 3);
+        assert_eq!(divergence.reference_line, 3);
+        assert_eq!(divergence.ours.as_deref(), Some("C004 A:02"));
+        assert_eq!(divergence.reference.as_deref(), Some("C004 A:FF"));
+        assert_eq!(divergence.context, vec!["C002 A:01"]);
+    }
+
+    #[test]
+    fn test_diff_reports_truncated_log() {
+        let divergence = diff("C000 A:00\n", "C000 A:00\nC002 A:01\n", 3, false)
+            .divergence
+            .unwrap();
+        assert_eq!(divergence.ours_line, 2);
+        assert_eq!(divergence.reference_line, 2);
+        assert_eq!(divergence.ours, None);
+        assert_eq!(divergence.reference.as_deref(), Some("C002 A:01"));
+    }
+
+    #[test]
+    fn test_diff_syncs_later_reference_start() {
+        let ours = "C004 A:02\nC006 A:03\n";
+        let reference = "C000 A:00\nC002 A:01\nC004 A:02\nC006 A:03\n";
+        let result = diff(ours, reference, 3, true);
+        assert_eq!(result.ours_skipped, 0);
+        assert_eq!(result.reference_skipped, 2);
+        assert!(result.divergence.is_none());
+    }
+
+    #[test]
+    fn test_diff_syncs_later_ours_start() {
+        let ours = "C000 A:00\nC002 A:01\nC004 A:02\nC006 A:FF\n";
+        let reference = "C002 A:01\nC004 A:02\nC006 A:03\n";
+        let result = diff(ours, reference, 3, true);
+        assert_eq!(result.ours_skipped, 1);
+        assert_eq!(result.reference_skipped, 0);
+        let divergence = result.divergence.unwrap();
+        assert_eq!(divergence.ours_line, 4);
+        assert_eq!(divergence.reference_line, 3);
+    }
+
+    #[test]
+    fn test_diff_first_line_mismatch() {
+        /* A later identical line must not hide the mismatch on line 1 */
+        let ours = "C000 A:01\nC002 A:02\n";
+        let reference = "C000 A:00\nC002 A:02\nC000 A:01\nC002 A:02\n";
+        let result = diff(ours, reference, 3, false);
+        assert_eq!(result.ours_skipped, 0);
+        assert_eq!(result.reference_skipped, 0);
+        let divergence = result.divergence.unwrap();
+        assert_eq!(divergence.ours_line, 1);
+        assert_eq!(divergence.reference_line, 1);
+        assert_eq!(divergence.ours.as_deref(), Some("C000 A:01"));
+        assert_eq!(divergence.reference.as_deref(), Some("C000 A:00"));
+    }
+}
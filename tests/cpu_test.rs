@@ -1,5 +1,6 @@
 extern crate nes_rs;
 use bitflags::bitflags;
+use nes_rs::asm::Asm;
 
 bitflags! {
     struct Status: u32 {
@@ -16,7 +17,7 @@ bitflags! {
 #[test]
 fn test_adc() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![0xa9, 0xfe, 0x69, 0x01, 0x00]);
+    cpu.load_and_run(Asm::new().lda_imm(0xfe).adc_imm(0x01).brk().assemble());
     assert_eq!(cpu.reg_a, 0xFF);
     assert_eq!(cpu.status & 0b0000_0001, 0x0);
 }
@@ -24,7 +25,7 @@ fn test_adc() {
 #[test]
 fn test_adc_carried() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![0xa9, 0xff, 0x69, 0x01, 0x00]);
+    cpu.load_and_run(Asm::new().lda_imm(0xff).adc_imm(0x01).brk().assemble());
     assert_eq!(cpu.reg_a, 0x00);
     assert_eq!(cpu.status & 0b0000_0001, 0b0000_00001);
 }
@@ -32,11 +33,7 @@ fn test_adc_carried() {
 #[test]
 fn test_adc_overflow() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0x80, /* lda #0x80 */
-        0x69, 0x80, /* adc #0x80*/
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(Asm::new().lda_imm(0x80).adc_imm(0x80).brk().assemble());
     assert_eq!(cpu.reg_a, 0x00);
     assert_eq!(cpu.status & 0b0000_0011, 0b0000_00011);
 }
@@ -44,11 +41,7 @@ fn test_adc_overflow() {
 #[test]
 fn test_sbc() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0xfe, /* lda #0xfe */
-        0xe9, 0x01, /* sbc #0x01 */
-        0x00,
-    ]);
+    cpu.load_and_run(Asm::new().lda_imm(0xfe).sbc_imm(0x01).brk().assemble());
     assert_eq!(cpu.reg_a, 0xfc);
     assert_eq!(cpu.status & 0b1000_0011, 0b10000001);
 }
@@ -56,33 +49,37 @@ fn test_sbc() {
 #[test]
 fn test_and() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![0xa9, 0xff, 0x29, 0x0f, 0x00]);
+    cpu.load_and_run(Asm::new().lda_imm(0xff).and_imm(0x0f).brk().assemble());
     assert_eq!(cpu.reg_a, 0x0f);
 }
 
 #[test]
 fn test_and_zero_page() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0xff, /* lda #0xff */
-        0x85, 0x00, /* sta zero */
-        0xa9, 0x0f, /* lda #0x0f */
-        0x25, 0x00, /* and zero */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0xff)
+            .sta_zp(0x00)
+            .lda_imm(0x0f)
+            .and_zp(0x00)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0x0f);
 }
 
 #[test]
 fn test_and_absolute() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0xf0, /* lda #0xf0 */
-        0x85, 0x00, /* sta zero */
-        0xa9, 0x0f, /* lda #0x0f */
-        0x2d, 0x00, 0x00, /* and absolute */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0xf0)
+            .sta_zp(0x00)
+            .lda_imm(0x0f)
+            .and_abs(0x0000)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0x00);
     assert_eq!(cpu.status & 0b0000_0010, 0b0000_0010);
 }
@@ -90,11 +87,7 @@ fn test_and_absolute() {
 #[test]
 fn test_asl() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0xf0, /* lda #0xf0 */
-        0x0a, /* asl accumulator */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(Asm::new().lda_imm(0xf0).asl_acc().brk().assemble());
     assert_eq!(cpu.reg_a, 0xe0);
     assert_eq!(cpu.status & 0b0000_0001, 0b0000_0001);
 }
@@ -102,13 +95,15 @@ fn test_asl() {
 #[test]
 fn test_asl_zero_page() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0x78, /* lda #0x78 */
-        0x85, 0x00, /* sta zero */
-        0x06, 0x00, /* asl zero */
-        0xa5, 0x00, /* lda zero */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0x78)
+            .sta_zp(0x00)
+            .asl_zp(0x00)
+            .lda_zp(0x00)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0xf0);
     assert_eq!(cpu.status & 0b1000_0000, 0b1000_0000);
 }
@@ -116,14 +111,17 @@ fn test_asl_zero_page() {
 #[test]
 fn test_branch() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0x00, /* lda #0x00 */
-        0xf0, 0x02, /* BEQ #0x02 */
-        0x00, /* BRK */
-        0x00, /* BRK */
-        0xa9, 0x0e, /* lda #0xee */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0x00)
+            .beq("skip")
+            .brk()
+            .brk()
+            .label("skip")
+            .lda_imm(0x0e)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0x0e);
     assert_eq!(cpu.status, 0x00);
 }
@@ -131,13 +129,15 @@ fn test_branch() {
 #[test]
 fn test_bit() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0xf0, /* lda #0xf0 */
-        0x85, 0x00, /* sta zero */
-        0xa9, 0x0f, /* lda #0x0f */
-        0x24, 0x00, /* bit zero */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0xf0)
+            .sta_zp(0x00)
+            .lda_imm(0x0f)
+            .bit_zp(0x00)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0x0f);
     assert_eq!(cpu.status & 0b1100_0010, 0b1100_0010);
 }
@@ -145,13 +145,15 @@ fn test_bit() {
 #[test]
 fn test_bit_non_zero_flg() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0xf0, /* lda #0xf0 */
-        0x85, 0x00, /* sta zero */
-        0xa9, 0xf0, /* lda #0xf0 */
-        0x24, 0x00, /* bit zero */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0xf0)
+            .sta_zp(0x00)
+            .lda_imm(0xf0)
+            .bit_zp(0x00)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0xf0);
     assert_eq!(cpu.status & 0b1100_0010, 0b1100_0000);
 }
@@ -159,12 +161,14 @@ fn test_bit_non_zero_flg() {
 #[test]
 fn test_clc() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0xff, /* lda #0xff */
-        0x69, 0x01, /* adc #0x01 */
-        0x18, /* CLC */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0xff)
+            .adc_imm(0x01)
+            .clc()
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0x00);
     assert_eq!(cpu.status & 0b0000_0001, 0b0000_0000);
 }
@@ -172,11 +176,7 @@ fn test_clc() {
 #[test]
 fn test_cmp() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0x12, /* lda #0x12 */
-        0xc9, 0x12, /* cmp #0x12 */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(Asm::new().lda_imm(0x12).cmp_imm(0x12).brk().assemble());
     assert_eq!(cpu.reg_a, 0x12);
     assert_eq!(cpu.status & 0b1000_0011, 0b0000_0011);
 }
@@ -184,13 +184,15 @@ fn test_cmp() {
 #[test]
 fn test_cmp_negative() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0x02, /* lda #0x02 */
-        0x85, 0x00, /* sta zero */
-        0xa9, 0xf2, /* lda #0xf2 */
-        0xc5, 0x00, /* cmp zero */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0x02)
+            .sta_zp(0x00)
+            .lda_imm(0xf2)
+            .cmp_zp(0x00)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0xf2);
     assert_eq!(cpu.status & 0b1000_0011, 0b1000_0001);
 }
@@ -198,7 +200,7 @@ fn test_cmp_negative() {
 #[test]
 fn test_ldx() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![0xa2, 0x02 /* ldx #0x02 */, 0x00, /* BRK */]);
+    cpu.load_and_run(Asm::new().ldx_imm(0x02).brk().assemble());
     assert_eq!(cpu.index_reg_x, 0x02);
     assert_eq!(cpu.status, 0x0);
 }
@@ -206,7 +208,7 @@ fn test_ldx() {
 #[test]
 fn test_ldy() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![0xa0, 0x02 /* ldy #0x02 */, 0x00 /* BRK */]);
+    cpu.load_and_run(Asm::new().ldy_imm(0x02).brk().assemble());
     assert_eq!(cpu.index_reg_y, 0x02);
     assert_eq!(cpu.status, 0x0);
 }
@@ -214,11 +216,7 @@ fn test_ldy() {
 #[test]
 fn test_cpx() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa2, 0x02, /* ldx #0x02 */
-        0xe0, 0x02, /* cpy #0x02 */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(Asm::new().ldx_imm(0x02).cpx_imm(0x02).brk().assemble());
     assert_eq!(cpu.index_reg_x, 0x02);
     assert_eq!(cpu.status & 0b1000_0011, 0b0000_0011);
 }
@@ -226,11 +224,7 @@ fn test_cpx() {
 #[test]
 fn test_cpy() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa0, 0x02, /* ldy #0x02 */
-        0xc0, 0x02, /* cpy #0x02 */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(Asm::new().ldy_imm(0x02).cpy_imm(0x02).brk().assemble());
     assert_eq!(cpu.index_reg_y, 0x02);
     assert_eq!(cpu.status & 0b1000_0011, 0b0000_0011);
 }
@@ -238,13 +232,15 @@ fn test_cpy() {
 #[test]
 fn test_dec() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0x04, /* lda #0x04 */
-        0x85, 0x00, /* sta zero */
-        0xc6, 0x00, /* dec zero */
-        0xa5, 0x00, /* lda zero */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0x04)
+            .sta_zp(0x00)
+            .dec_zp(0x00)
+            .lda_zp(0x00)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0x03);
     assert_eq!(cpu.status & 0b0100_0001, 0b0000_0000);
 }
@@ -252,11 +248,7 @@ fn test_dec() {
 #[test]
 fn test_dex() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa2, 0x01, /* ldx #0x01 */
-        0xca, /* dex */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(Asm::new().ldx_imm(0x01).dex().brk().assemble());
     assert_eq!(cpu.index_reg_x, 0x00);
     assert_eq!(cpu.status & 0b1000_0010, 0b0000_0010);
 }
@@ -264,11 +256,7 @@ fn test_dex() {
 #[test]
 fn test_dey() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa0, 0x01, /* ldy #0x01 */
-        0x88, /* dey */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(Asm::new().ldy_imm(0x01).dey().brk().assemble());
     assert_eq!(cpu.index_reg_y, 0x00);
     assert_eq!(cpu.status & 0b1000_0010, 0b0000_0010);
 }
@@ -276,13 +264,15 @@ fn test_dey() {
 #[test]
 fn test_eor() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0xff, /* lda #0xff */
-        0x85, 0x00, /* sta zero */
-        0xa9, 0x0f, /* lda #0x0f */
-        0x45, 0x00, /* eor zero */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0xff)
+            .sta_zp(0x00)
+            .lda_imm(0x0f)
+            .eor_zp(0x00)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0xf0);
     assert_eq!(cpu.status & 0b1000_0010, 0b1000_0000);
 }
@@ -290,13 +280,15 @@ fn test_eor() {
 #[test]
 fn test_inc() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0x04, /* lda #0x04 */
-        0x85, 0x00, /* sta zero */
-        0xe6, 0x00, /* dec zero */
-        0xa5, 0x00, /* lda zero */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .lda_imm(0x04)
+            .sta_zp(0x00)
+            .inc_zp(0x00)
+            .lda_zp(0x00)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0x05);
     assert_eq!(cpu.status & 0b0100_0001, 0b0000_0000);
 }
@@ -304,11 +296,7 @@ fn test_inc() {
 #[test]
 fn test_ora() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0x04, /* lda #0x04 */
-        0x09, 0x40, /* ora #0x40 */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(Asm::new().lda_imm(0x04).ora_imm(0x40).brk().assemble());
     assert_eq!(cpu.reg_a, 0x44);
     assert_eq!(cpu.status & 0b0100_0001, 0b0000_0000);
 }
@@ -316,11 +304,7 @@ fn test_ora() {
 #[test]
 fn test_rol() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0xf0, /* lda #0xf0 */
-        0x2a, /* rol */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(Asm::new().lda_imm(0xf0).rol_acc().brk().assemble());
     assert_eq!(cpu.reg_a, 0xe1);
     assert_eq!(cpu.status & 0b1100_0001, 0b1000_0001);
 }
@@ -328,11 +312,7 @@ fn test_rol() {
 #[test]
 fn test_ror() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa9, 0xf0, /* lda #0xf0 */
-        0x6a, /* ror */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(Asm::new().lda_imm(0xf0).ror_acc().brk().assemble());
     assert_eq!(cpu.reg_a, 0x78);
     assert_eq!(cpu.status & 0b1100_0001, 0b0000_0000);
 }
@@ -340,23 +320,27 @@ fn test_ror() {
 #[test]
 fn test_stx() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa2, 0x02, /* ldx #0x02 */
-        0x86, 0x00, /* stx zero */
-        0xa5, 0x00, /* lda zero */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .ldx_imm(0x02)
+            .stx_zp(0x00)
+            .lda_zp(0x00)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0x02);
 }
 
 #[test]
 fn test_sty() {
     let mut cpu = nes_rs::cpu::CPU::new();
-    cpu.load_and_run(vec![
-        0xa0, 0x02, /* ldy #0x02 */
-        0x84, 0x00, /* sty zero */
-        0xa5, 0x00, /* lda zero */
-        0x00, /* BRK */
-    ]);
+    cpu.load_and_run(
+        Asm::new()
+            .ldy_imm(0x02)
+            .sty_zp(0x00)
+            .lda_zp(0x00)
+            .brk()
+            .assemble(),
+    );
     assert_eq!(cpu.reg_a, 0x02);
-}
\ No newline at end of file
+}
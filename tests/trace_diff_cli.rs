@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::{env, fs, process};
+
+/* Removes the log file when the test finishes, pass or fail */
+struct TempLog(PathBuf);
+
+impl TempLog {
+    fn new(name: &str, contents: &str) -> Self {
+        let path = env::temp_dir().join(format!("nes-rs-{}-{}.log", process::id(), name));
+        fs::write(&path, contents).unwrap();
+        TempLog(path)
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempLog {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn trace_diff(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_nes-rs"))
+        .arg("trace-diff")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_trace_diff_match() {
+    let ours = TempLog::new("match-ours", "C000 A:00 CYC:7\nC002 A:01 CYC:9\n");
+    let reference = TempLog::new("match-ref", "C000 A:00 CYC:8\nC002 A:01 CYC:10\n");
+    let output = trace_diff(&[ours.path(), reference.path()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("traces match"));
+}
+
+#[test]
+fn test_trace_diff_divergence() {
+    let ours = TempLog::new("diverge-ours", "C000 A:00\nC002 A:01\nC004 A:02\n");
+    let reference = TempLog::new("diverge-ref", "C000 A:00\nC002 A:01\nC004 A:03\n");
+    let output = trace_diff(&[ours.path(), reference.path()]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("first divergence at line 3 (reference line 3)"));
+    assert!(stdout.contains("-  C004 A:02"));
+    assert!(stdout.contains("+  C004 A:03"));
+}
+
+#[test]
+fn test_trace_diff_first_line_mismatch_without_sync() {
+    let ours = TempLog::new("first-ours", "C000 A:01\nC002 A:02\n");
+    let reference = TempLog::new("first-ref", "C000 A:00\nC002 A:02\nC000 A:01\nC002 A:02\n");
+    let output = trace_diff(&[ours.path(), reference.path()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("first divergence at line 1"));
+}
+
+#[test]
+fn test_trace_diff_sync_warns_on_skip() {
+    let ours = TempLog::new("sync-ours", "C002 A:01\nC004 A:02\n");
+    let reference = TempLog::new("sync-ref", "C000 A:00\nC002 A:01\nC004 A:02\n");
+    let output = trace_diff(&["--sync", ours.path(), reference.path()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("warning: skipped 1 lines of reference log to sync"));
+}
+
+#[test]
+fn test_trace_diff_usage_error() {
+    let output = trace_diff(&["only-one.log"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("usage:"));
+}
+
+#[test]
+fn test_trace_diff_missing_file() {
+    let output = trace_diff(&["/nonexistent/ours.log", "/nonexistent/ref.log"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to read"));
+}